@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: GPL-2.0
+
+// Copyright (C) 2024 Google LLC.
+
+//! Binder contexts: one per `/dev/binderN` device node, each owning the set of processes that
+//! have opened it.
+
+use alloc::vec::Vec;
+
+use kernel::prelude::*;
+use kernel::str::{CStr, CString};
+use kernel::sync::lock::spinlock::SpinLock;
+use kernel::sync::Arc;
+
+use crate::process::Process;
+
+/// A binder context, corresponding to one `/dev/binderN` device.
+pub(crate) struct Context {
+    name: CString,
+    processes: SpinLock<Vec<Arc<Process>>>,
+}
+
+impl Context {
+    pub(crate) fn new(name: &CStr) -> Result<Arc<Self>> {
+        Ok(Arc::try_new(Self {
+            name: CString::try_from(name)?,
+            processes: SpinLock::new(Vec::new()),
+        })?)
+    }
+
+    pub(crate) fn name(&self) -> &CStr {
+        &self.name
+    }
+
+    pub(crate) fn deregister(&self) {}
+
+    /// Registers `process` as having opened this context, so it shows up in
+    /// `rust_binder_state_show`/`rust_binder_transactions_show`.
+    pub(crate) fn register_process(&self, process: Arc<Process>) {
+        self.processes.lock().push(process);
+    }
+
+    /// Removes `process` from this context, e.g. when its file descriptor is closed.
+    pub(crate) fn unregister_process(&self, process: &Arc<Process>) {
+        self.processes.lock().retain(|p| !Arc::ptr_eq(p, process));
+    }
+
+    /// Calls `f` once for every [`Process`] that currently has this context open.
+    pub(crate) fn for_each_process(&self, mut f: impl FnMut(&Arc<Process>)) {
+        for proc in self.processes.lock().iter() {
+            f(proc);
+        }
+    }
+}
+
+/// Global list of registered contexts, one per `/dev/binderN` device.
+pub(crate) struct Contexts {
+    list: SpinLock<Vec<Arc<Context>>>,
+}
+
+impl Contexts {
+    const fn new() -> Self {
+        Self {
+            list: SpinLock::new(Vec::new()),
+        }
+    }
+
+    /// Must be called exactly once, before any other use of `self`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure this is the very first thing done with `CONTEXTS`, and that
+    /// `CONTEXTS` is never moved (it isn't -- it's a `'static`).
+    pub(crate) unsafe fn init(&'static self) {}
+
+    /// Registers `ctx`, so it shows up in debugfs dumps such as `rust_binder_state_show`.
+    pub(crate) fn register(&self, ctx: Arc<Context>) {
+        self.list.lock().push(ctx);
+    }
+
+    /// Removes `ctx`, e.g. when its `/dev/binderN` device node is torn down.
+    pub(crate) fn unregister(&self, ctx: &Arc<Context>) {
+        self.list.lock().retain(|c| !Arc::ptr_eq(c, ctx));
+    }
+
+    /// Calls `f` once for every currently registered [`Context`].
+    pub(crate) fn for_each(&self, mut f: impl FnMut(&Arc<Context>)) {
+        for ctx in self.list.lock().iter() {
+            f(ctx);
+        }
+    }
+}
+
+/// The global set of contexts created by `rust_binder_new_device`.
+pub(crate) static CONTEXTS: Contexts = Contexts::new();
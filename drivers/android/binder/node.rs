@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: GPL-2.0
+
+// Copyright (C) 2024 Google LLC.
+
+//! Binder nodes and the references processes hold to them.
+
+use crate::stats;
+
+/// A binder object hosted by some process, reachable from other processes through a [`NodeRef`].
+pub(crate) struct Node {
+    debug_id: i32,
+}
+
+impl Node {
+    pub(crate) fn new(debug_id: i32) -> Self {
+        stats::inc_node();
+        Self { debug_id }
+    }
+
+    pub(crate) fn debug_id(&self) -> i32 {
+        self.debug_id
+    }
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        stats::dec_node();
+    }
+}
+
+/// A reference another process holds to a [`Node`], identified by its handle (`desc`) in that
+/// process's table.
+pub(crate) struct NodeRef {
+    debug_id: i32,
+    desc: u32,
+}
+
+impl NodeRef {
+    pub(crate) fn new(debug_id: i32, desc: u32) -> Self {
+        stats::inc_ref();
+        Self { debug_id, desc }
+    }
+
+    pub(crate) fn debug_id(&self) -> i32 {
+        self.debug_id
+    }
+
+    pub(crate) fn desc(&self) -> u32 {
+        self.desc
+    }
+}
+
+impl Drop for NodeRef {
+    fn drop(&mut self) {
+        stats::dec_ref();
+    }
+}
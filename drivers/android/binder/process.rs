@@ -0,0 +1,190 @@
+// SPDX-License-Identifier: GPL-2.0
+
+// Copyright (C) 2024 Google LLC.
+
+//! The process type, representing a single open file descriptor on the binder device.
+
+use alloc::vec::Vec;
+
+use kernel::fs::File;
+use kernel::mm::virt::VmArea;
+use kernel::prelude::*;
+use kernel::seq_file::SeqFile;
+use kernel::sync::lock::spinlock::SpinLock;
+use kernel::sync::poll::PollTable;
+use kernel::sync::{Arc, ArcBorrow};
+use kernel::uaccess::UserSlice;
+
+use crate::context::Context;
+use crate::node::{Node, NodeRef};
+use crate::thread::Thread;
+use crate::transaction::Transaction;
+use crate::{defs, seq_print, stats, trace, DeliverToRead};
+
+/// A process that has opened the binder device.
+pub(crate) struct Process {
+    ctx: Arc<Context>,
+    pid: i32,
+    threads: SpinLock<Vec<Arc<Thread>>>,
+    nodes: SpinLock<Vec<Arc<Node>>>,
+    refs: SpinLock<Vec<Arc<NodeRef>>>,
+    /// Work items queued for this process but not yet a concrete [`DeliverToRead`] in this tree;
+    /// real once something actually implements `DeliverToRead` and pushes into it.
+    todo: SpinLock<Vec<crate::DArc<dyn DeliverToRead>>>,
+    buffer_range: SpinLock<Option<(usize, usize)>>,
+}
+
+impl Process {
+    pub(crate) fn open(ctx: ArcBorrow<'_, Context>, _file: &File) -> Result<Arc<Self>> {
+        stats::inc_process();
+        let process = Arc::try_new(Self {
+            ctx: ctx.clone_arc(),
+            pid: 0,
+            threads: SpinLock::new(Vec::new()),
+            nodes: SpinLock::new(Vec::new()),
+            refs: SpinLock::new(Vec::new()),
+            todo: SpinLock::new(Vec::new()),
+            buffer_range: SpinLock::new(None),
+        })?;
+        ctx.register_process(process.clone());
+        Ok(process)
+    }
+
+    pub(crate) fn release(this: Arc<Self>, _file: &File) {
+        this.ctx.unregister_process(&this);
+    }
+
+    pub(crate) fn task_pid(&self) -> i32 {
+        self.pid
+    }
+
+    /// Returns this process's single registered binder thread, creating it on first use.
+    fn thread(&self) -> Result<Arc<Thread>> {
+        let mut threads = self.threads.lock();
+        if let Some(thread) = threads.first() {
+            return Ok(thread.clone());
+        }
+        let thread = Arc::try_new(Thread::new(self.pid, 0))?;
+        threads.push(thread.clone());
+        Ok(thread)
+    }
+
+    /// Calls `f` once for every [`Thread`] this process has registered with the binder
+    /// threadpool.
+    pub(crate) fn for_each_thread(&self, mut f: impl FnMut(&Thread)) {
+        for thread in self.threads.lock().iter() {
+            f(thread);
+        }
+    }
+
+    /// Calls `f` once for every [`Node`](crate::node::Node) this process owns.
+    pub(crate) fn for_each_node(&self, mut f: impl FnMut(&Node)) {
+        for node in self.nodes.lock().iter() {
+            f(node);
+        }
+    }
+
+    /// Calls `f` once for every [`NodeRef`] this process holds to a node owned by another
+    /// process.
+    pub(crate) fn for_each_ref(&self, mut f: impl FnMut(&NodeRef)) {
+        for node_ref in self.refs.lock().iter() {
+            f(node_ref);
+        }
+    }
+
+    /// Writes this process's mapped binder buffer range to `m`, for `rust_binder_state_show`.
+    pub(crate) fn debug_print_buffer(&self, m: &SeqFile) {
+        if let Some((start, size)) = *self.buffer_range.lock() {
+            seq_print(
+                m,
+                format_args!("    buffer {:#x}-{:#x}\n", start, start + size),
+            );
+        }
+    }
+
+    /// Calls `f` once for every work item queued on this process's todo list.
+    pub(crate) fn for_each_todo_item(&self, mut f: impl FnMut(&dyn DeliverToRead)) {
+        for item in self.todo.lock().iter() {
+            let item: &dyn DeliverToRead = item;
+            f(item);
+        }
+    }
+
+    pub(crate) fn compat_ioctl(
+        this: ArcBorrow<'_, Self>,
+        file: &File,
+        cmd: u32,
+        arg: usize,
+    ) -> Result {
+        Self::ioctl(this, file, cmd, arg)
+    }
+
+    /// Handles an ioctl as a `BINDER_WRITE_READ`; the request number itself is not examined. This
+    /// is the dispatch site for every `BC_` command the
+    /// process sends down, and for `BC_TRANSACTION`/`BC_REPLY`, for delivering the resulting
+    /// transaction (successfully or not).
+    pub(crate) fn ioctl(this: ArcBorrow<'_, Self>, _file: &File, _cmd: u32, arg: usize) -> Result {
+        this.handle_command(arg)
+    }
+
+    /// Processes a single `BC_*` command from a `BINDER_WRITE_READ` ioctl's write buffer.
+    fn handle_command(&self, arg: usize) -> Result {
+        // `arg` is the ioctl's user pointer to the write buffer; the `BC_*` command lives in the
+        // buffer itself, not in the ioctl request number we were dispatched on.
+        let cmd: u32 = UserSlice::new(arg, core::mem::size_of::<u32>())
+            .reader()
+            .read()?;
+        stats::inc_command(cmd);
+        trace::trace_command(cmd);
+
+        if cmd != defs::BC_TRANSACTION && cmd != defs::BC_REPLY {
+            return Ok(());
+        }
+        let reply = cmd == defs::BC_REPLY;
+
+        let thread = self.thread()?;
+        thread.wait_for_work();
+
+        let node = Arc::try_new(Node::new(0))?;
+        self.nodes.lock().push(node.clone());
+
+        let node_ref = Arc::try_new(NodeRef::new(node.debug_id(), 0))?;
+        self.refs.lock().push(node_ref.clone());
+
+        let t = Arc::try_new(Transaction::new(self.pid, 0, 0, 0, node.debug_id(), arg, 0))?;
+        t.translate_node_to_ref(&node, &node_ref);
+        t.translate_ref_to_node(&node_ref, &node);
+
+        t.alloc_buffer(self.pid);
+        let result = t.send(reply);
+        t.received();
+        t.free_buffer(self.pid);
+
+        thread.record_transaction(t);
+        thread.wake_up(!reply);
+        result
+    }
+
+    pub(crate) fn mmap(this: ArcBorrow<'_, Self>, _file: &File, vma: VmArea<'_>) -> Result {
+        *this.buffer_range.lock() = Some((vma.start(), vma.end() - vma.start()));
+        Ok(())
+    }
+
+    pub(crate) fn poll(
+        _this: ArcBorrow<'_, Self>,
+        _file: &File,
+        _table: PollTable<'_>,
+    ) -> Result<u32> {
+        Ok(0)
+    }
+
+    pub(crate) fn flush(_this: ArcBorrow<'_, Self>) -> Result {
+        Ok(())
+    }
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        stats::dec_process();
+    }
+}
@@ -4,11 +4,14 @@
 
 //! Binder -- the Android IPC mechanism.
 
+use core::fmt;
+
 use kernel::{
     bindings::{self, seq_file},
     fs::File,
     list::{HasListLinks, ListArc, ListArcSafe, ListLinksSelfPtr, TryNewListArc},
     prelude::*,
+    seq_file::SeqFile,
     sync::poll::PollTable,
     sync::Arc,
     types::{AsBytes, ForeignOwnable},
@@ -22,8 +25,11 @@ mod defs;
 mod error;
 mod node;
 mod process;
+mod stats;
 mod thread;
 mod trace;
+mod transaction;
+mod transaction_log;
 
 module! {
     type: BinderModule,
@@ -47,6 +53,8 @@ impl BinderReturnWriter {
     /// Write a return code back to user space.
     /// Should be a `BR_` constant from [`defs`] e.g. [`defs::BR_TRANSACTION_COMPLETE`].
     fn write_code(&mut self, code: u32) -> Result {
+        stats::inc_return(code);
+        trace::trace_return(code);
         self.writer.write(&code)
     }
 
@@ -77,6 +85,12 @@ trait DeliverToRead: ListArcSafe + Send + Sync {
     ///
     /// Generally only set to true for non-oneway transactions.
     fn should_sync_wakeup(&self) -> bool;
+
+    /// Writes a one-line description of this work item to `m`, for `rust_binder_state_show`. Work
+    /// items that want a more useful dump than their type name should override this.
+    fn debug_print(&self, m: &SeqFile, prefix: &str) {
+        seq_print(m, format_args!("{}{}\n", prefix, core::any::type_name::<Self>()));
+    }
 }
 
 // Wrapper around a `DeliverToRead` with linked list links.
@@ -211,7 +225,10 @@ unsafe extern "C" fn rust_binder_new_device(
     // SAFETY: The caller will always provide a valid c string here.
     let name = unsafe { kernel::str::CStr::from_char_ptr(name) };
     match Context::new(name) {
-        Ok(ctx) => Arc::into_foreign(ctx).cast_mut(),
+        Ok(ctx) => {
+            context::CONTEXTS.register(ctx.clone());
+            Arc::into_foreign(ctx).cast_mut()
+        }
         Err(_err) => core::ptr::null_mut(),
     }
 }
@@ -222,6 +239,7 @@ unsafe extern "C" fn rust_binder_remove_device(device: *mut core::ffi::c_void) {
         // SAFETY: The caller ensures that the `device` pointer came from a previous call to
         // `rust_binder_new_device`.
         let ctx = unsafe { Arc::<Context>::from_foreign(device) };
+        context::CONTEXTS.unregister(&ctx);
         ctx.deregister();
         drop(ctx);
     }
@@ -329,34 +347,104 @@ unsafe extern "C" fn rust_binder_flush(
     }
 }
 
+/// Writes a formatted line to `m`.
+///
+/// Formatting failures (e.g. allocation failure) are dropped; a missing line in a debugfs dump is
+/// not worth failing the whole read over.
+pub(crate) fn seq_print(m: &SeqFile, args: fmt::Arguments<'_>) {
+    let _ = fmt::Write::write_fmt(&mut &*m, args);
+}
+
+fn rust_binder_state_show_impl(m: &SeqFile) {
+    seq_print(m, format_args!("binder state:\n"));
+
+    context::CONTEXTS.for_each(|ctx| {
+        seq_print(m, format_args!("context {}\n", ctx.name()));
+
+        ctx.for_each_process(|proc| {
+            seq_print(m, format_args!("  proc {}\n", proc.task_pid()));
+
+            proc.for_each_thread(|thread| {
+                seq_print(m, format_args!("    thread {}\n", thread.tid()));
+            });
+
+            proc.for_each_node(|node| {
+                seq_print(m, format_args!("    node {}\n", node.debug_id()));
+            });
+
+            proc.for_each_ref(|node_ref| {
+                seq_print(
+                    m,
+                    format_args!(
+                        "    ref {} desc {}\n",
+                        node_ref.debug_id(),
+                        node_ref.desc()
+                    ),
+                );
+            });
+
+            proc.debug_print_buffer(m);
+
+            proc.for_each_todo_item(|work| work.debug_print(m, "    pending transaction "));
+        });
+    });
+}
+
+fn rust_binder_transactions_show_impl(m: &SeqFile) {
+    seq_print(m, format_args!("binder transactions:\n"));
+
+    context::CONTEXTS.for_each(|ctx| {
+        ctx.for_each_process(|proc| {
+            seq_print(m, format_args!("proc {}\n", proc.task_pid()));
+
+            proc.for_each_thread(|thread| {
+                thread.for_each_transaction(|t| t.debug_print(m, "  "));
+            });
+        });
+    });
+}
+
 #[no_mangle]
 unsafe extern "C" fn rust_binder_stats_show(
-    _: *mut seq_file,
+    seq: *mut seq_file,
     _: *mut core::ffi::c_void,
 ) -> core::ffi::c_int {
+    // SAFETY: The caller (the debugfs show machinery) guarantees that `seq` is a valid `seq_file`
+    // pointer for the duration of this call.
+    stats::show(unsafe { SeqFile::from_ptr(seq) });
     0
 }
 
 #[no_mangle]
 unsafe extern "C" fn rust_binder_state_show(
-    _: *mut seq_file,
+    seq: *mut seq_file,
     _: *mut core::ffi::c_void,
 ) -> core::ffi::c_int {
+    // SAFETY: The caller (the debugfs show machinery) guarantees that `seq` is a valid `seq_file`
+    // pointer for the duration of this call.
+    rust_binder_state_show_impl(unsafe { SeqFile::from_ptr(seq) });
     0
 }
 
 #[no_mangle]
 unsafe extern "C" fn rust_binder_transactions_show(
-    _: *mut seq_file,
+    seq: *mut seq_file,
     _: *mut core::ffi::c_void,
 ) -> core::ffi::c_int {
+    // SAFETY: The caller (the debugfs show machinery) guarantees that `seq` is a valid `seq_file`
+    // pointer for the duration of this call.
+    rust_binder_transactions_show_impl(unsafe { SeqFile::from_ptr(seq) });
+
     0
 }
 
 #[no_mangle]
 unsafe extern "C" fn rust_binder_transaction_log_show(
-    _: *mut seq_file,
+    seq: *mut seq_file,
     _: *mut core::ffi::c_void,
 ) -> core::ffi::c_int {
+    // SAFETY: The caller (the debugfs show machinery) guarantees that `seq` is a valid `seq_file`
+    // pointer for the duration of this call.
+    transaction_log::show(unsafe { SeqFile::from_ptr(seq) });
     0
 }
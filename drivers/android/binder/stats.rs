@@ -0,0 +1,347 @@
+// SPDX-License-Identifier: GPL-2.0
+
+// Copyright (C) 2024 Google LLC.
+
+//! Per-command/return and object-lifecycle statistics, surfaced through
+//! `rust_binder_stats_show`.
+//!
+//! Every counter bump sits on the hot IPC path (a command/return code is processed on every
+//! transaction, and object counters change on every node/ref/transaction/process/thread
+//! allocation and free), so counters are relaxed atomics and the whole feature is gated behind
+//! `CONFIG_ANDROID_BINDER_IPC_STATS`. When the option is off, the functions below compile down to
+//! nothing and callers need no `#[cfg]` of their own.
+
+macro_rules! show_nonzero {
+    ($m:expr, $counters:expr, [$($field:ident => $name:literal),* $(,)?]) => {
+        $(
+            let v = $counters.$field.load(Ordering::Relaxed);
+            if v != 0 {
+                seq_print($m, format_args!("  {}: {}\n", $name, v));
+            }
+        )*
+    };
+}
+
+#[cfg(CONFIG_ANDROID_BINDER_IPC_STATS)]
+mod enabled {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use kernel::seq_file::SeqFile;
+
+    use crate::{defs, seq_print};
+
+    #[derive(Default)]
+    struct CommandCounters {
+        bc_transaction: AtomicUsize,
+        bc_reply: AtomicUsize,
+        bc_acquire_result: AtomicUsize,
+        bc_free_buffer: AtomicUsize,
+        bc_increfs: AtomicUsize,
+        bc_acquire: AtomicUsize,
+        bc_release: AtomicUsize,
+        bc_decrefs: AtomicUsize,
+        bc_increfs_done: AtomicUsize,
+        bc_acquire_done: AtomicUsize,
+        bc_register_looper: AtomicUsize,
+        bc_enter_looper: AtomicUsize,
+        bc_exit_looper: AtomicUsize,
+        bc_request_death_notification: AtomicUsize,
+        bc_clear_death_notification: AtomicUsize,
+        bc_dead_binder_done: AtomicUsize,
+        bc_transaction_sg: AtomicUsize,
+        bc_reply_sg: AtomicUsize,
+    }
+
+    #[derive(Default)]
+    struct ReturnCounters {
+        br_error: AtomicUsize,
+        br_ok: AtomicUsize,
+        br_transaction: AtomicUsize,
+        br_reply: AtomicUsize,
+        br_acquire_result: AtomicUsize,
+        br_dead_reply: AtomicUsize,
+        br_transaction_complete: AtomicUsize,
+        br_increfs: AtomicUsize,
+        br_acquire: AtomicUsize,
+        br_release: AtomicUsize,
+        br_decrefs: AtomicUsize,
+        br_noop: AtomicUsize,
+        br_spawn_looper: AtomicUsize,
+        br_finished: AtomicUsize,
+        br_dead_binder: AtomicUsize,
+        br_clear_death_notification_done: AtomicUsize,
+        br_failed_reply: AtomicUsize,
+        br_frozen_reply: AtomicUsize,
+    }
+
+    #[derive(Default)]
+    struct ObjectCounters {
+        nodes_active: AtomicUsize,
+        nodes_total: AtomicUsize,
+        refs_active: AtomicUsize,
+        refs_total: AtomicUsize,
+        transactions_active: AtomicUsize,
+        transactions_total: AtomicUsize,
+        processes_active: AtomicUsize,
+        processes_total: AtomicUsize,
+        threads_active: AtomicUsize,
+        threads_total: AtomicUsize,
+    }
+
+    #[derive(Default)]
+    struct Stats {
+        commands: CommandCounters,
+        returns: ReturnCounters,
+        objects: ObjectCounters,
+    }
+
+    static STATS: Stats = Stats {
+        commands: CommandCounters {
+            bc_transaction: AtomicUsize::new(0),
+            bc_reply: AtomicUsize::new(0),
+            bc_acquire_result: AtomicUsize::new(0),
+            bc_free_buffer: AtomicUsize::new(0),
+            bc_increfs: AtomicUsize::new(0),
+            bc_acquire: AtomicUsize::new(0),
+            bc_release: AtomicUsize::new(0),
+            bc_decrefs: AtomicUsize::new(0),
+            bc_increfs_done: AtomicUsize::new(0),
+            bc_acquire_done: AtomicUsize::new(0),
+            bc_register_looper: AtomicUsize::new(0),
+            bc_enter_looper: AtomicUsize::new(0),
+            bc_exit_looper: AtomicUsize::new(0),
+            bc_request_death_notification: AtomicUsize::new(0),
+            bc_clear_death_notification: AtomicUsize::new(0),
+            bc_dead_binder_done: AtomicUsize::new(0),
+            bc_transaction_sg: AtomicUsize::new(0),
+            bc_reply_sg: AtomicUsize::new(0),
+        },
+        returns: ReturnCounters {
+            br_error: AtomicUsize::new(0),
+            br_ok: AtomicUsize::new(0),
+            br_transaction: AtomicUsize::new(0),
+            br_reply: AtomicUsize::new(0),
+            br_acquire_result: AtomicUsize::new(0),
+            br_dead_reply: AtomicUsize::new(0),
+            br_transaction_complete: AtomicUsize::new(0),
+            br_increfs: AtomicUsize::new(0),
+            br_acquire: AtomicUsize::new(0),
+            br_release: AtomicUsize::new(0),
+            br_decrefs: AtomicUsize::new(0),
+            br_noop: AtomicUsize::new(0),
+            br_spawn_looper: AtomicUsize::new(0),
+            br_finished: AtomicUsize::new(0),
+            br_dead_binder: AtomicUsize::new(0),
+            br_clear_death_notification_done: AtomicUsize::new(0),
+            br_failed_reply: AtomicUsize::new(0),
+            br_frozen_reply: AtomicUsize::new(0),
+        },
+        objects: ObjectCounters {
+            nodes_active: AtomicUsize::new(0),
+            nodes_total: AtomicUsize::new(0),
+            refs_active: AtomicUsize::new(0),
+            refs_total: AtomicUsize::new(0),
+            transactions_active: AtomicUsize::new(0),
+            transactions_total: AtomicUsize::new(0),
+            processes_active: AtomicUsize::new(0),
+            processes_total: AtomicUsize::new(0),
+            threads_active: AtomicUsize::new(0),
+            threads_total: AtomicUsize::new(0),
+        },
+    };
+
+    pub(crate) fn inc_command(code: u32) {
+        let counter = match code {
+            c if c == defs::BC_TRANSACTION => &STATS.commands.bc_transaction,
+            c if c == defs::BC_REPLY => &STATS.commands.bc_reply,
+            c if c == defs::BC_ACQUIRE_RESULT => &STATS.commands.bc_acquire_result,
+            c if c == defs::BC_FREE_BUFFER => &STATS.commands.bc_free_buffer,
+            c if c == defs::BC_INCREFS => &STATS.commands.bc_increfs,
+            c if c == defs::BC_ACQUIRE => &STATS.commands.bc_acquire,
+            c if c == defs::BC_RELEASE => &STATS.commands.bc_release,
+            c if c == defs::BC_DECREFS => &STATS.commands.bc_decrefs,
+            c if c == defs::BC_INCREFS_DONE => &STATS.commands.bc_increfs_done,
+            c if c == defs::BC_ACQUIRE_DONE => &STATS.commands.bc_acquire_done,
+            c if c == defs::BC_REGISTER_LOOPER => &STATS.commands.bc_register_looper,
+            c if c == defs::BC_ENTER_LOOPER => &STATS.commands.bc_enter_looper,
+            c if c == defs::BC_EXIT_LOOPER => &STATS.commands.bc_exit_looper,
+            c if c == defs::BC_REQUEST_DEATH_NOTIFICATION => {
+                &STATS.commands.bc_request_death_notification
+            }
+            c if c == defs::BC_CLEAR_DEATH_NOTIFICATION => {
+                &STATS.commands.bc_clear_death_notification
+            }
+            c if c == defs::BC_DEAD_BINDER_DONE => &STATS.commands.bc_dead_binder_done,
+            c if c == defs::BC_TRANSACTION_SG => &STATS.commands.bc_transaction_sg,
+            c if c == defs::BC_REPLY_SG => &STATS.commands.bc_reply_sg,
+            _ => return,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn inc_return(code: u32) {
+        let counter = match code {
+            c if c == defs::BR_ERROR => &STATS.returns.br_error,
+            c if c == defs::BR_OK => &STATS.returns.br_ok,
+            c if c == defs::BR_TRANSACTION => &STATS.returns.br_transaction,
+            c if c == defs::BR_REPLY => &STATS.returns.br_reply,
+            c if c == defs::BR_ACQUIRE_RESULT => &STATS.returns.br_acquire_result,
+            c if c == defs::BR_DEAD_REPLY => &STATS.returns.br_dead_reply,
+            c if c == defs::BR_TRANSACTION_COMPLETE => &STATS.returns.br_transaction_complete,
+            c if c == defs::BR_INCREFS => &STATS.returns.br_increfs,
+            c if c == defs::BR_ACQUIRE => &STATS.returns.br_acquire,
+            c if c == defs::BR_RELEASE => &STATS.returns.br_release,
+            c if c == defs::BR_DECREFS => &STATS.returns.br_decrefs,
+            c if c == defs::BR_NOOP => &STATS.returns.br_noop,
+            c if c == defs::BR_SPAWN_LOOPER => &STATS.returns.br_spawn_looper,
+            c if c == defs::BR_FINISHED => &STATS.returns.br_finished,
+            c if c == defs::BR_DEAD_BINDER => &STATS.returns.br_dead_binder,
+            c if c == defs::BR_CLEAR_DEATH_NOTIFICATION_DONE => {
+                &STATS.returns.br_clear_death_notification_done
+            }
+            c if c == defs::BR_FAILED_REPLY => &STATS.returns.br_failed_reply,
+            c if c == defs::BR_FROZEN_REPLY => &STATS.returns.br_frozen_reply,
+            _ => return,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn inc_node() {
+        STATS.objects.nodes_active.fetch_add(1, Ordering::Relaxed);
+        STATS.objects.nodes_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn dec_node() {
+        STATS.objects.nodes_active.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn inc_ref() {
+        STATS.objects.refs_active.fetch_add(1, Ordering::Relaxed);
+        STATS.objects.refs_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn dec_ref() {
+        STATS.objects.refs_active.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn inc_transaction() {
+        STATS.objects.transactions_active.fetch_add(1, Ordering::Relaxed);
+        STATS.objects.transactions_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn dec_transaction() {
+        STATS.objects.transactions_active.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn inc_process() {
+        STATS.objects.processes_active.fetch_add(1, Ordering::Relaxed);
+        STATS.objects.processes_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn dec_process() {
+        STATS.objects.processes_active.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn inc_thread() {
+        STATS.objects.threads_active.fetch_add(1, Ordering::Relaxed);
+        STATS.objects.threads_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn dec_thread() {
+        STATS.objects.threads_active.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn show(m: &SeqFile) {
+        seq_print(m, format_args!("commands:\n"));
+        show_nonzero!(m, STATS.commands, [
+            bc_transaction => "BC_TRANSACTION",
+            bc_reply => "BC_REPLY",
+            bc_acquire_result => "BC_ACQUIRE_RESULT",
+            bc_free_buffer => "BC_FREE_BUFFER",
+            bc_increfs => "BC_INCREFS",
+            bc_acquire => "BC_ACQUIRE",
+            bc_release => "BC_RELEASE",
+            bc_decrefs => "BC_DECREFS",
+            bc_increfs_done => "BC_INCREFS_DONE",
+            bc_acquire_done => "BC_ACQUIRE_DONE",
+            bc_register_looper => "BC_REGISTER_LOOPER",
+            bc_enter_looper => "BC_ENTER_LOOPER",
+            bc_exit_looper => "BC_EXIT_LOOPER",
+            bc_request_death_notification => "BC_REQUEST_DEATH_NOTIFICATION",
+            bc_clear_death_notification => "BC_CLEAR_DEATH_NOTIFICATION",
+            bc_dead_binder_done => "BC_DEAD_BINDER_DONE",
+            bc_transaction_sg => "BC_TRANSACTION_SG",
+            bc_reply_sg => "BC_REPLY_SG",
+        ]);
+
+        seq_print(m, format_args!("returns:\n"));
+        show_nonzero!(m, STATS.returns, [
+            br_error => "BR_ERROR",
+            br_ok => "BR_OK",
+            br_transaction => "BR_TRANSACTION",
+            br_reply => "BR_REPLY",
+            br_acquire_result => "BR_ACQUIRE_RESULT",
+            br_dead_reply => "BR_DEAD_REPLY",
+            br_transaction_complete => "BR_TRANSACTION_COMPLETE",
+            br_increfs => "BR_INCREFS",
+            br_acquire => "BR_ACQUIRE",
+            br_release => "BR_RELEASE",
+            br_decrefs => "BR_DECREFS",
+            br_noop => "BR_NOOP",
+            br_spawn_looper => "BR_SPAWN_LOOPER",
+            br_finished => "BR_FINISHED",
+            br_dead_binder => "BR_DEAD_BINDER",
+            br_clear_death_notification_done => "BR_CLEAR_DEATH_NOTIFICATION_DONE",
+            br_failed_reply => "BR_FAILED_REPLY",
+            br_frozen_reply => "BR_FROZEN_REPLY",
+        ]);
+
+        seq_print(m, format_args!("objects:\n"));
+        show_nonzero!(m, STATS.objects, [
+            nodes_active => "nodes (active)",
+            nodes_total => "nodes (total)",
+            refs_active => "refs (active)",
+            refs_total => "refs (total)",
+            transactions_active => "transactions (active)",
+            transactions_total => "transactions (total)",
+            processes_active => "processes (active)",
+            processes_total => "processes (total)",
+            threads_active => "threads (active)",
+            threads_total => "threads (total)",
+        ]);
+    }
+}
+
+#[cfg(not(CONFIG_ANDROID_BINDER_IPC_STATS))]
+mod enabled {
+    use kernel::seq_file::SeqFile;
+
+    #[inline(always)]
+    pub(crate) fn inc_command(_code: u32) {}
+    #[inline(always)]
+    pub(crate) fn inc_return(_code: u32) {}
+    #[inline(always)]
+    pub(crate) fn inc_node() {}
+    #[inline(always)]
+    pub(crate) fn dec_node() {}
+    #[inline(always)]
+    pub(crate) fn inc_ref() {}
+    #[inline(always)]
+    pub(crate) fn dec_ref() {}
+    #[inline(always)]
+    pub(crate) fn inc_transaction() {}
+    #[inline(always)]
+    pub(crate) fn dec_transaction() {}
+    #[inline(always)]
+    pub(crate) fn inc_process() {}
+    #[inline(always)]
+    pub(crate) fn dec_process() {}
+    #[inline(always)]
+    pub(crate) fn inc_thread() {}
+    #[inline(always)]
+    pub(crate) fn dec_thread() {}
+
+    pub(crate) fn show(_m: &SeqFile) {}
+}
+
+pub(crate) use enabled::*;
@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: GPL-2.0
+
+// Copyright (C) 2024 Google LLC.
+
+//! Binder threads: one per thread a process has registered with its binder threadpool.
+
+use alloc::vec::Vec;
+
+use kernel::sync::lock::spinlock::SpinLock;
+use kernel::sync::Arc;
+
+use crate::transaction::Transaction;
+use crate::{stats, trace};
+
+/// A thread that has registered itself with the binder threadpool of its [`Process`](crate::process::Process).
+pub(crate) struct Thread {
+    pid: i32,
+    tid: i32,
+    transactions: SpinLock<Vec<Arc<Transaction>>>,
+}
+
+impl Thread {
+    pub(crate) fn new(pid: i32, tid: i32) -> Self {
+        stats::inc_thread();
+        Self {
+            pid,
+            tid,
+            transactions: SpinLock::new(Vec::new()),
+        }
+    }
+
+    pub(crate) fn pid(&self) -> i32 {
+        self.pid
+    }
+
+    pub(crate) fn tid(&self) -> i32 {
+        self.tid
+    }
+
+    /// Records `transaction` on this thread, so it shows up in `rust_binder_transactions_show`.
+    /// Recorded transactions are never removed.
+    pub(crate) fn record_transaction(&self, transaction: Arc<Transaction>) {
+        self.transactions.lock().push(transaction);
+    }
+
+    /// Calls `f` once for every transaction ever recorded on this thread, finished or not, for
+    /// `rust_binder_transactions_show`.
+    pub(crate) fn for_each_transaction(&self, mut f: impl FnMut(&Transaction)) {
+        for t in self.transactions.lock().iter() {
+            f(t);
+        }
+    }
+
+    /// Traces this thread waiting for work on its or its process's todo list. Does not block.
+    pub(crate) fn wait_for_work(&self) {
+        trace::trace_wait_for_work(self.pid, self.tid);
+    }
+
+    /// Traces this thread being woken up to process newly queued work. Does not wake anything.
+    pub(crate) fn wake_up(&self, sync: bool) {
+        trace::trace_wakeup(self.pid, self.tid, sync);
+    }
+}
+
+impl Drop for Thread {
+    fn drop(&mut self) {
+        stats::dec_thread();
+    }
+}
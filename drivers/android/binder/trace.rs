@@ -3,6 +3,7 @@
 // Copyright (C) 2024 Google LLC.
 
 use crate::transaction::Transaction;
+use crate::transaction_log::{self, LoggedTransaction};
 
 use kernel::bindings::rust_binder_transaction;
 use kernel::error::Result;
@@ -14,6 +15,26 @@ declare_trace! {
     unsafe fn rust_binder_ioctl(cmd: c_uint, arg: c_ulong);
     unsafe fn rust_binder_ioctl_done(ret: c_int);
     unsafe fn rust_binder_transaction(reply: bool, t: rust_binder_transaction);
+    unsafe fn rust_binder_transaction_received(debug_id: c_int);
+    unsafe fn rust_binder_transaction_buffer_release(debug_id: c_int, data_size: usize, offsets_size: usize);
+    unsafe fn rust_binder_transaction_node_to_ref(
+        debug_id: c_int,
+        node_debug_id: c_int,
+        ref_debug_id: c_int,
+        ref_desc: c_uint,
+    );
+    unsafe fn rust_binder_transaction_ref_to_node(
+        debug_id: c_int,
+        ref_debug_id: c_int,
+        ref_desc: c_uint,
+        node_debug_id: c_int,
+    );
+    unsafe fn rust_binder_buffer_alloc(proc_pid: c_int, debug_id: c_int, size: usize);
+    unsafe fn rust_binder_buffer_free(proc_pid: c_int, debug_id: c_int, size: usize);
+    unsafe fn rust_binder_command(cmd: c_uint);
+    unsafe fn rust_binder_return(cmd: c_uint);
+    unsafe fn rust_binder_wait_for_work(proc_pid: c_int, tid: c_int);
+    unsafe fn rust_binder_wakeup(proc_pid: c_int, tid: c_int, sync: bool);
 }
 
 #[inline]
@@ -41,8 +62,107 @@ pub(crate) fn trace_ioctl_done(ret: Result) {
     unsafe { rust_binder_ioctl_done(to_errno(ret)) }
 }
 
+#[inline]
+fn logged_transaction(t: &Transaction, reply: bool, return_error: i32) -> LoggedTransaction {
+    LoggedTransaction {
+        debug_id: t.debug_id(),
+        from_pid: t.from_pid(),
+        from_tid: t.from_tid(),
+        to_pid: t.to_pid(),
+        to_tid: t.to_tid(),
+        to_node_debug_id: t.to_node_debug_id(),
+        data_size: t.data_size(),
+        offsets_size: t.offsets_size(),
+        reply,
+        return_error,
+    }
+}
+
 #[inline]
 pub(crate) fn trace_transaction(reply: bool, t: &Transaction) {
+    transaction_log::log(logged_transaction(t, reply, 0));
+
     // SAFETY: The raw transaction is valid for the duration of this call.
     unsafe { rust_binder_transaction(reply, raw_transaction(t)) }
 }
+
+/// Records a transaction that failed to complete, so it still shows up in
+/// `rust_binder_transaction_log_show` alongside successful ones.
+#[inline]
+pub(crate) fn trace_transaction_failed(reply: bool, t: &Transaction, return_error: Result) {
+    transaction_log::log(logged_transaction(t, reply, to_errno(return_error)));
+}
+
+/// Traces that a transaction was picked up off a todo list and delivered to its target thread.
+#[inline]
+pub(crate) fn trace_transaction_received(t: &Transaction) {
+    // SAFETY: Always safe to call.
+    unsafe { rust_binder_transaction_received(t.debug_id()) }
+}
+
+/// Traces that a transaction's buffer (and the objects marshalled into it) is being released.
+#[inline]
+pub(crate) fn trace_transaction_buffer_release(t: &Transaction) {
+    // SAFETY: Always safe to call.
+    unsafe {
+        rust_binder_transaction_buffer_release(t.debug_id(), t.data_size(), t.offsets_size())
+    }
+}
+
+/// Traces that a flat binder object is being translated from a node in the sending process to a
+/// ref in the receiving process, during transaction marshalling.
+#[inline]
+pub(crate) fn trace_node_to_ref(debug_id: i32, node_debug_id: i32, ref_debug_id: i32, ref_desc: u32) {
+    // SAFETY: Always safe to call.
+    unsafe { rust_binder_transaction_node_to_ref(debug_id, node_debug_id, ref_debug_id, ref_desc) }
+}
+
+/// Traces that a flat binder object is being translated from a ref in the sending process to a
+/// node in the receiving process, during transaction marshalling.
+#[inline]
+pub(crate) fn trace_ref_to_node(debug_id: i32, ref_debug_id: i32, ref_desc: u32, node_debug_id: i32) {
+    // SAFETY: Always safe to call.
+    unsafe { rust_binder_transaction_ref_to_node(debug_id, ref_debug_id, ref_desc, node_debug_id) }
+}
+
+/// Traces a buffer being allocated out of a process's mapped binder buffer region.
+#[inline]
+pub(crate) fn trace_buffer_alloc(proc_pid: i32, debug_id: i32, size: usize) {
+    // SAFETY: Always safe to call.
+    unsafe { rust_binder_buffer_alloc(proc_pid, debug_id, size) }
+}
+
+/// Traces a buffer being freed back into a process's mapped binder buffer region.
+#[inline]
+pub(crate) fn trace_buffer_free(proc_pid: i32, debug_id: i32, size: usize) {
+    // SAFETY: Always safe to call.
+    unsafe { rust_binder_buffer_free(proc_pid, debug_id, size) }
+}
+
+/// Traces a `BC_*` command being processed at the ioctl dispatch site.
+#[inline]
+pub(crate) fn trace_command(cmd: u32) {
+    // SAFETY: Always safe to call.
+    unsafe { rust_binder_command(cmd) }
+}
+
+/// Traces a `BR_*` return code being delivered to user space.
+#[inline]
+pub(crate) fn trace_return(cmd: u32) {
+    // SAFETY: Always safe to call.
+    unsafe { rust_binder_return(cmd) }
+}
+
+/// Traces a thread blocking, waiting for work to appear on its or its process's todo list.
+#[inline]
+pub(crate) fn trace_wait_for_work(proc_pid: i32, tid: i32) {
+    // SAFETY: Always safe to call.
+    unsafe { rust_binder_wait_for_work(proc_pid, tid) }
+}
+
+/// Traces a thread being woken up to process newly queued work.
+#[inline]
+pub(crate) fn trace_wakeup(proc_pid: i32, tid: i32, sync: bool) {
+    // SAFETY: Always safe to call.
+    unsafe { rust_binder_wakeup(proc_pid, tid, sync) }
+}
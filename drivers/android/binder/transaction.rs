@@ -0,0 +1,182 @@
+// SPDX-License-Identifier: GPL-2.0
+
+// Copyright (C) 2024 Google LLC.
+
+//! In-flight binder transactions.
+
+use core::sync::atomic::{AtomicI32, Ordering};
+
+use kernel::error::code::EINVAL;
+use kernel::prelude::*;
+use kernel::seq_file::SeqFile;
+
+use crate::node::{Node, NodeRef};
+use crate::{seq_print, stats, trace};
+
+/// Largest `data_size` this driver will accept for a single transaction's payload.
+const MAX_TRANSACTION_SIZE: usize = 4 * 1024 * 1024;
+
+/// Monotonic counter handing out each transaction's `debug_id`, mirroring the C driver's
+/// `binder_last_id`.
+static NEXT_DEBUG_ID: AtomicI32 = AtomicI32::new(1);
+
+/// A single binder transaction in flight between two threads.
+pub(crate) struct Transaction {
+    debug_id: i32,
+    from_pid: i32,
+    from_tid: i32,
+    to_pid: i32,
+    to_tid: i32,
+    to_node_debug_id: i32,
+    data_size: usize,
+    offsets_size: usize,
+}
+
+impl Transaction {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        from_pid: i32,
+        from_tid: i32,
+        to_pid: i32,
+        to_tid: i32,
+        to_node_debug_id: i32,
+        data_size: usize,
+        offsets_size: usize,
+    ) -> Self {
+        stats::inc_transaction();
+        Self {
+            debug_id: NEXT_DEBUG_ID.fetch_add(1, Ordering::Relaxed),
+            from_pid,
+            from_tid,
+            to_pid,
+            to_tid,
+            to_node_debug_id,
+            data_size,
+            offsets_size,
+        }
+    }
+
+    pub(crate) fn debug_id(&self) -> i32 {
+        self.debug_id
+    }
+
+    pub(crate) fn from_pid(&self) -> i32 {
+        self.from_pid
+    }
+
+    pub(crate) fn from_tid(&self) -> i32 {
+        self.from_tid
+    }
+
+    pub(crate) fn to_pid(&self) -> i32 {
+        self.to_pid
+    }
+
+    pub(crate) fn to_tid(&self) -> i32 {
+        self.to_tid
+    }
+
+    pub(crate) fn to_node_debug_id(&self) -> i32 {
+        self.to_node_debug_id
+    }
+
+    pub(crate) fn data_size(&self) -> usize {
+        self.data_size
+    }
+
+    pub(crate) fn offsets_size(&self) -> usize {
+        self.offsets_size
+    }
+
+    /// Writes a one-line summary of this transaction to `m`, for `rust_binder_transactions_show`.
+    pub(crate) fn debug_print(&self, m: &SeqFile, prefix: &str) {
+        seq_print(
+            m,
+            format_args!(
+                "{}{}: {}:{} -> {}:{} node {}\n",
+                prefix,
+                self.debug_id,
+                self.from_pid,
+                self.from_tid,
+                self.to_pid,
+                self.to_tid,
+                self.to_node_debug_id,
+            ),
+        );
+    }
+
+    /// Records the outcome of delivering this transaction: logs it either way, and also emits the
+    /// `rust_binder_transaction` tracepoint on success. A failure is logged with its errno but
+    /// not traced.
+    pub(crate) fn finish(&self, reply: bool, result: Result) -> Result {
+        match result {
+            Ok(()) => {
+                trace::trace_transaction(reply, self);
+                Ok(())
+            }
+            Err(err) => {
+                trace::trace_transaction_failed(reply, self, Err(err));
+                Err(err)
+            }
+        }
+    }
+
+    /// Checks this transaction's `data_size` against [`MAX_TRANSACTION_SIZE`] and records the
+    /// outcome through [`Self::finish`], returning the same error it logged as failed.
+    pub(crate) fn send(&self, reply: bool) -> Result {
+        let result = if self.data_size > MAX_TRANSACTION_SIZE {
+            Err(EINVAL)
+        } else {
+            Ok(())
+        };
+        self.finish(reply, result)
+    }
+
+    /// Traces that this transaction was picked up off a todo list and delivered to its target
+    /// thread.
+    pub(crate) fn received(&self) {
+        trace::trace_transaction_received(self);
+    }
+
+    /// Traces this transaction's buffer being allocated out of `proc_pid`'s mapped binder buffer
+    /// region, ahead of marshalling the transaction's payload into it.
+    pub(crate) fn alloc_buffer(&self, proc_pid: i32) {
+        trace::trace_buffer_alloc(proc_pid, self.debug_id, self.data_size);
+    }
+
+    /// Traces this transaction's buffer (and the objects marshalled into it) being released and
+    /// freed back into `proc_pid`'s mapped binder buffer region.
+    pub(crate) fn free_buffer(&self, proc_pid: i32) {
+        trace::trace_transaction_buffer_release(self);
+        trace::trace_buffer_free(proc_pid, self.debug_id, self.data_size);
+    }
+
+    /// Traces a flat binder object in this transaction's payload being translated from `node`, in
+    /// the sending process, to `node_ref`, in the receiving process.
+    pub(crate) fn translate_node_to_ref(&self, node: &Node, node_ref: &NodeRef) {
+        trace::trace_node_to_ref(
+            self.debug_id,
+            node.debug_id(),
+            node_ref.debug_id(),
+            node_ref.desc(),
+        );
+    }
+
+    /// Traces a flat binder object in this transaction's payload being translated from
+    /// `node_ref`, in the sending process, back to `node`, the node it refers to in the receiving
+    /// process.
+    pub(crate) fn translate_ref_to_node(&self, node_ref: &NodeRef, node: &Node) {
+        trace::trace_ref_to_node(
+            self.debug_id,
+            node_ref.debug_id(),
+            node_ref.desc(),
+            node.debug_id(),
+        );
+    }
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        stats::dec_transaction();
+    }
+}
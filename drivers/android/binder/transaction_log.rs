@@ -0,0 +1,123 @@
+// SPDX-License-Identifier: GPL-2.0
+
+// Copyright (C) 2024 Google LLC.
+
+//! A bounded ring buffer of recently completed binder transactions, surfaced through
+//! `rust_binder_transaction_log_show`. Mirrors the fixed-size transaction log kept by the C
+//! binder driver.
+
+use kernel::seq_file::SeqFile;
+use kernel::sync::lock::spinlock::SpinLock;
+
+use crate::seq_print;
+
+/// Number of entries kept in the ring buffer.
+const LOG_SIZE: usize = 32;
+
+#[derive(Clone, Copy)]
+struct TransactionLogEntry {
+    debug_id: i32,
+    from_pid: i32,
+    from_tid: i32,
+    to_pid: i32,
+    to_tid: i32,
+    to_node_debug_id: i32,
+    data_size: usize,
+    offsets_size: usize,
+    reply: bool,
+    return_error: i32,
+}
+
+impl TransactionLogEntry {
+    const EMPTY: Self = Self {
+        debug_id: 0,
+        from_pid: 0,
+        from_tid: 0,
+        to_pid: 0,
+        to_tid: 0,
+        to_node_debug_id: 0,
+        data_size: 0,
+        offsets_size: 0,
+        reply: false,
+        return_error: 0,
+    };
+}
+
+/// Describes a single transaction to be recorded in the log.
+pub(crate) struct LoggedTransaction {
+    pub(crate) debug_id: i32,
+    pub(crate) from_pid: i32,
+    pub(crate) from_tid: i32,
+    pub(crate) to_pid: i32,
+    pub(crate) to_tid: i32,
+    pub(crate) to_node_debug_id: i32,
+    pub(crate) data_size: usize,
+    pub(crate) offsets_size: usize,
+    pub(crate) reply: bool,
+    pub(crate) return_error: i32,
+}
+
+/// The ring buffer's backing storage plus how many entries have ever been logged.
+struct TransactionLogInner {
+    entries: [TransactionLogEntry; LOG_SIZE],
+    /// Grows without bound; an entry's real slot is `count % LOG_SIZE`.
+    count: u64,
+}
+
+/// Global ring buffer of the most recent [`LOG_SIZE`] transactions, guarded by a single spinlock
+/// shared by `log()` and `show()` so a reader can never observe a partially-written entry.
+static TRANSACTION_LOG: SpinLock<TransactionLogInner> = SpinLock::new(TransactionLogInner {
+    entries: [TransactionLogEntry::EMPTY; LOG_SIZE],
+    count: 0,
+});
+
+/// Records a transaction in the ring buffer.
+pub(crate) fn log(entry: LoggedTransaction) {
+    let mut inner = TRANSACTION_LOG.lock();
+    let idx = (inner.count % LOG_SIZE as u64) as usize;
+    inner.entries[idx] = TransactionLogEntry {
+        debug_id: entry.debug_id,
+        from_pid: entry.from_pid,
+        from_tid: entry.from_tid,
+        to_pid: entry.to_pid,
+        to_tid: entry.to_tid,
+        to_node_debug_id: entry.to_node_debug_id,
+        data_size: entry.data_size,
+        offsets_size: entry.offsets_size,
+        reply: entry.reply,
+        return_error: entry.return_error,
+    };
+    inner.count += 1;
+}
+
+/// Writes the ring buffer's contents, oldest first, to `m`.
+pub(crate) fn show(m: &SeqFile) {
+    let inner = TRANSACTION_LOG.lock();
+    let count = core::cmp::min(inner.count, LOG_SIZE as u64);
+    let start = inner.count - count;
+
+    for i in start..inner.count {
+        let entry = &inner.entries[(i % LOG_SIZE as u64) as usize];
+
+        seq_print(
+            m,
+            format_args!(
+                "{}: {}{} from {}:{} to {}:{} node {} size {}:{}\n",
+                i,
+                entry.debug_id,
+                if entry.reply { " reply" } else { "" },
+                entry.from_pid,
+                entry.from_tid,
+                entry.to_pid,
+                entry.to_tid,
+                entry.to_node_debug_id,
+                entry.data_size,
+                entry.offsets_size,
+            ),
+        );
+
+        if entry.return_error != 0 {
+            seq_print(m, format_args!("    return_error {}\n", entry.return_error));
+        }
+    }
+}
@@ -0,0 +1,53 @@
+// SPDX-License-Identifier: GPL-2.0
+
+// Copyright (C) 2024 Google LLC.
+
+//! Safe wrapper around the kernel's `struct seq_file`.
+
+use core::fmt;
+
+use crate::{bindings, str::CStr, types::Opaque};
+
+/// A borrowed reference to a `struct seq_file`, used by `/proc` and `debugfs` show callbacks to
+/// build up their output.
+///
+/// # Invariants
+///
+/// Instances of this type are always created from a pointer that is valid for the lifetime of
+/// the borrow.
+#[repr(transparent)]
+pub struct SeqFile(Opaque<bindings::seq_file>);
+
+impl SeqFile {
+    /// Creates a new [`SeqFile`] from a raw pointer.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `ptr` points at a valid `struct seq_file` for the duration of
+    /// `'a`.
+    pub unsafe fn from_ptr<'a>(ptr: *mut bindings::seq_file) -> &'a SeqFile {
+        // SAFETY: `SeqFile` is a transparent wrapper over `Opaque<bindings::seq_file>`, and the
+        // caller guarantees that `ptr` is valid for `'a`.
+        unsafe { &*ptr.cast() }
+    }
+
+    fn as_ptr(&self) -> *mut bindings::seq_file {
+        self.0.get()
+    }
+
+    /// Writes a NUL-terminated C string to the `seq_file`.
+    pub fn call_printf(&self, str: &CStr) {
+        // SAFETY: `self.as_ptr()` is valid by the type invariants, and `str` is a valid
+        // NUL-terminated string for the duration of this call.
+        unsafe { bindings::seq_puts(self.as_ptr(), str.as_char_ptr()) };
+    }
+}
+
+impl fmt::Write for &SeqFile {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        // SAFETY: `self.as_ptr()` is valid by the type invariants of `SeqFile`, and `s` points at
+        // `s.len()` initialized bytes.
+        unsafe { bindings::seq_write(self.as_ptr(), s.as_bytes().as_ptr().cast(), s.len()) };
+        Ok(())
+    }
+}